@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+use crate::midi::MidiState;
+
+/// Actions that can be bound to a global (system-wide) hotkey.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum HotkeyAction {
+  Panic,
+  ToggleAlwaysOnTop,
+  OctaveUp,
+  OctaveDown,
+  ShowWindow,
+}
+
+impl HotkeyAction {
+  fn default_accelerator(self) -> &'static str {
+    match self {
+      HotkeyAction::Panic => "CmdOrCtrl+Shift+P",
+      HotkeyAction::ToggleAlwaysOnTop => "CmdOrCtrl+Shift+T",
+      HotkeyAction::OctaveUp => "CmdOrCtrl+Shift+Up",
+      HotkeyAction::OctaveDown => "CmdOrCtrl+Shift+Down",
+      HotkeyAction::ShowWindow => "CmdOrCtrl+Shift+K",
+    }
+  }
+}
+
+/// The built-in accelerator for every action, used to seed `Settings` and
+/// as a fallback for any action missing from a loaded settings file.
+pub fn default_bindings() -> HashMap<HotkeyAction, String> {
+  [
+    HotkeyAction::Panic,
+    HotkeyAction::ToggleAlwaysOnTop,
+    HotkeyAction::OctaveUp,
+    HotkeyAction::OctaveDown,
+    HotkeyAction::ShowWindow,
+  ]
+  .into_iter()
+  .map(|action| (action, action.default_accelerator().to_string()))
+  .collect()
+}
+
+/// Hotkey bindings, keyed by action, mapped to an accelerator string
+/// understood by `tauri-plugin-global-shortcut`.
+pub struct HotkeyState {
+  bindings: Mutex<HashMap<HotkeyAction, String>>,
+}
+
+impl HotkeyState {
+  pub fn new(bindings: HashMap<HotkeyAction, String>) -> Self {
+    Self { bindings: Mutex::new(bindings) }
+  }
+}
+
+impl Default for HotkeyState {
+  fn default() -> Self {
+    Self::new(default_bindings())
+  }
+}
+
+/// Register every bound hotkey with the global-shortcut plugin, dropping any
+/// previous registrations first.
+pub fn register_all(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+  let state: State<HotkeyState> = app.state();
+  let bindings = state.bindings.lock().unwrap().clone();
+
+  let shortcut = app.global_shortcut();
+  shortcut.unregister_all()?;
+  for accelerator in bindings.values() {
+    shortcut.register(accelerator.as_str())?;
+  }
+
+  Ok(())
+}
+
+/// Handle a fired global shortcut, dispatching to the bound action.
+///
+/// Bindings are stored as accelerator strings (e.g. `"CmdOrCtrl+Shift+P"`),
+/// but `CmdOrCtrl` and friends are parse-time aliases that never appear in a
+/// fired `Shortcut`'s own textual form — so each stored accelerator is
+/// re-parsed into a `Shortcut` and compared by value, not by string.
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut) {
+  let state: State<HotkeyState> = app.state();
+  let action = {
+    let bindings = state.bindings.lock().unwrap();
+    bindings
+      .iter()
+      .find(|(_, accelerator)| accelerator.parse::<Shortcut>().map(|bound| &bound == shortcut).unwrap_or(false))
+      .map(|(action, _)| *action)
+  };
+
+  let Some(action) = action else { return };
+
+  match action {
+    HotkeyAction::Panic => {
+      let midi: State<MidiState> = app.state();
+      for channel in 0..16u8 {
+        let _ = midi.send(&[0xb0 | channel, 123, 0]);
+      }
+    }
+    _ => {
+      let _ = app.emit("hotkey", action);
+    }
+  }
+}
+
+#[tauri::command]
+pub fn set_hotkey(app: AppHandle, state: State<HotkeyState>, action: HotkeyAction, accelerator: String) -> Result<(), String> {
+  // Reject unparsable accelerators before touching the committed bindings.
+  accelerator.parse::<Shortcut>().map_err(|e| e.to_string())?;
+
+  let previous = {
+    let mut bindings = state.bindings.lock().unwrap();
+    let previous = bindings.clone();
+    bindings.insert(action, accelerator);
+    previous
+  };
+
+  if let Err(e) = register_all(&app) {
+    // The new binding didn't take (e.g. OS/another app already owns it);
+    // roll back so `bindings` keeps matching what's actually registered.
+    *state.bindings.lock().unwrap() = previous;
+    let _ = register_all(&app);
+    return Err(e.to_string());
+  }
+
+  let bindings = state.bindings.lock().unwrap().clone();
+  let _ = crate::settings::update(&app, |settings| settings.hotkeys = bindings);
+  Ok(())
+}
+
+#[tauri::command]
+pub fn get_hotkeys(state: State<HotkeyState>) -> HashMap<HotkeyAction, String> {
+  state.bindings.lock().unwrap().clone()
+}
@@ -1,20 +1,92 @@
 use tauri::{
-  AppHandle, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
-  menu::{Menu, MenuItem, PredefinedMenuItem},
-  tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+  AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+  menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+  tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
 };
 use std::sync::{Arc, Mutex};
 
+const MIDI_CHANNEL_COUNT: u8 = 16;
+
+mod hotkeys;
+mod midi;
+mod settings;
+mod updater;
+
+use hotkeys::HotkeyState;
+use midi::MidiState;
+use settings::SettingsState;
+use updater::UpdaterState;
+
 #[derive(Clone)]
 struct AppState {
   always_on_top: Arc<Mutex<bool>>,
+  really_quit: Arc<Mutex<bool>>,
+}
+
+/// Holds the live tray icon and the handles of the menu items whose label or
+/// checked state changes at runtime, so `refresh_tray_menu` can update them
+/// in place instead of rebuilding the whole menu.
+#[derive(Default)]
+struct TrayState {
+  tray: Mutex<Option<TrayIcon<tauri::Wry>>>,
+  show_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+  midi_status_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+  check_update_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+  channel_items: Mutex<Vec<CheckMenuItem<tauri::Wry>>>,
+  active_channel: Mutex<u8>,
+}
+
+/// Refresh the parts of the tray menu that depend on live state: the
+/// Show/Hide label, the connected MIDI device line, the checked channel, and
+/// the "Check for Updates…" / "Restart to Update" label.
+fn refresh_tray_menu(app: &AppHandle) {
+  let tray_state: State<TrayState> = app.state();
+
+  if let Some(show_item) = tray_state.show_item.lock().unwrap().as_ref() {
+    let visible = app
+      .get_webview_window("main")
+      .map(|w| w.is_visible().unwrap_or(false))
+      .unwrap_or(false);
+    let _ = show_item.set_text(if visible { "Hide MIDI Controller" } else { "Show MIDI Controller" });
+  }
+
+  if let Some(midi_status_item) = tray_state.midi_status_item.lock().unwrap().as_ref() {
+    let midi_state: State<MidiState> = app.state();
+    let label = match midi_state.connected_port_name() {
+      Some(name) => format!("Connected: {name}"),
+      None => "Not connected".to_string(),
+    };
+    let _ = midi_status_item.set_text(label);
+  }
+
+  let active_channel = *tray_state.active_channel.lock().unwrap();
+  for (index, item) in tray_state.channel_items.lock().unwrap().iter().enumerate() {
+    let _ = item.set_checked(index as u8 == active_channel);
+  }
+
+  if let Some(check_update_item) = tray_state.check_update_item.lock().unwrap().as_ref() {
+    let updater_state: State<UpdaterState> = app.state();
+    let ready = *updater_state.ready.lock().unwrap();
+    let _ = check_update_item.set_text(if ready { "Restart to Update" } else { "Check for Updates…" });
+  }
+}
+
+/// Set the MIDI channel selected from the tray's Channel submenu, update the
+/// checkmarks, and notify the frontend.
+fn set_active_channel(app: &AppHandle, channel: u8) {
+  let tray_state: State<TrayState> = app.state();
+  *tray_state.active_channel.lock().unwrap() = channel;
+  refresh_tray_menu(app);
+  let _ = settings::update(app, |settings| settings.active_channel = channel);
+  let _ = app.emit("channel-selected", channel);
 }
 
 #[tauri::command]
-fn toggle_always_on_top(window: WebviewWindow, state: State<AppState>) -> Result<bool, String> {
+fn toggle_always_on_top(app: AppHandle, window: WebviewWindow, state: State<AppState>) -> Result<bool, String> {
   let mut always_on_top = state.always_on_top.lock().unwrap();
   *always_on_top = !*always_on_top;
   window.set_always_on_top(*always_on_top).map_err(|e| e.to_string())?;
+  let _ = settings::update(&app, |settings| settings.always_on_top = *always_on_top);
   Ok(*always_on_top)
 }
 
@@ -25,33 +97,91 @@ fn show_window(window: WebviewWindow) -> Result<(), String> {
   Ok(())
 }
 
-fn create_tray_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, Box<dyn std::error::Error>> {
+/// The handles of menu items `refresh_tray_menu` needs to mutate later,
+/// alongside the menu they belong to.
+struct TrayMenu {
+  menu: Menu<tauri::Wry>,
+  show_item: MenuItem<tauri::Wry>,
+  midi_status_item: MenuItem<tauri::Wry>,
+  check_update_item: MenuItem<tauri::Wry>,
+  channel_items: Vec<CheckMenuItem<tauri::Wry>>,
+}
+
+fn create_tray_menu(app: &AppHandle) -> Result<TrayMenu, Box<dyn std::error::Error>> {
+  let visible = app
+    .get_webview_window("main")
+    .map(|w| w.is_visible().unwrap_or(false))
+    .unwrap_or(false);
+  let show_label = if visible { "Hide MIDI Controller" } else { "Show MIDI Controller" };
+  let show_item = MenuItem::with_id(app, "show", show_label, true, None::<&str>)?;
+
   let toggle_item = MenuItem::with_id(app, "always_on_top", "Always on Top", true, Some("always-on-top"))?;
-  let show_item = MenuItem::with_id(app, "show", "Show MIDI Controller", true, None::<&str>)?;
+
+  let midi_state: State<MidiState> = app.state();
+  let midi_label = match midi_state.connected_port_name() {
+    Some(name) => format!("Connected: {name}"),
+    None => "Not connected".to_string(),
+  };
+  let midi_status_item = MenuItem::with_id(app, "midi_status", midi_label, false, None::<&str>)?;
+
+  let tray_state: State<TrayState> = app.state();
+  let active_channel = *tray_state.active_channel.lock().unwrap();
+  let mut channel_items = Vec::with_capacity(MIDI_CHANNEL_COUNT as usize);
+  for channel in 0..MIDI_CHANNEL_COUNT {
+    let item = CheckMenuItem::with_id(
+      app,
+      format!("channel_{channel}"),
+      format!("Channel {}", channel + 1),
+      true,
+      channel == active_channel,
+      None::<&str>,
+    )?;
+    channel_items.push(item);
+  }
+  let channel_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> =
+    channel_items.iter().map(|item| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>).collect();
+  let channel_submenu = Submenu::with_items(app, "Channel", true, &channel_refs)?;
+
+  let updater_state: State<UpdaterState> = app.state();
+  let update_ready = *updater_state.ready.lock().unwrap();
+  let update_label = if update_ready { "Restart to Update" } else { "Check for Updates…" };
+  let check_update_item = MenuItem::with_id(app, "check_update", update_label, true, None::<&str>)?;
+
   let quit_item = MenuItem::with_id(app, "quit", "Quit", true, Some("q"))?;
 
   let menu = Menu::with_items(app, &[
     &show_item,
     &toggle_item,
     &PredefinedMenuItem::separator(app)?,
+    &midi_status_item,
+    &channel_submenu,
+    &PredefinedMenuItem::separator(app)?,
+    &check_update_item,
+    &PredefinedMenuItem::separator(app)?,
     &quit_item,
   ])?;
 
-  Ok(menu)
+  Ok(TrayMenu { menu, show_item, midi_status_item, check_update_item, channel_items })
 }
 
 fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-  let menu = create_tray_menu(app)?;
+  let tray_menu = create_tray_menu(app)?;
+  let menu = tray_menu.menu;
 
-  let _tray = TrayIconBuilder::new()
+  let tray = TrayIconBuilder::new()
     .icon(app.default_window_icon().unwrap().clone())
     .menu(&menu)
     .on_menu_event(|app, event| {
       match event.id().as_ref() {
         "show" => {
           if let Some(window) = app.get_webview_window("main") {
-            let _ = window.show();
-            let _ = window.set_focus();
+            if window.is_visible().unwrap_or(false) {
+              let _ = window.hide();
+            } else {
+              let _ = window.show();
+              let _ = window.set_focus();
+            }
+            refresh_tray_menu(app);
           }
         }
         "always_on_top" => {
@@ -60,11 +190,25 @@ fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
             let mut always_on_top = state.always_on_top.lock().unwrap();
             *always_on_top = !*always_on_top;
             let _ = window.set_always_on_top(*always_on_top);
+            let _ = settings::update(app, |settings| settings.always_on_top = *always_on_top);
           }
         }
+        "check_update" => {
+          let app = app.clone();
+          tauri::async_runtime::spawn(async move {
+            let _ = updater::check_for_updates(app).await;
+          });
+        }
         "quit" => {
+          let state: State<AppState> = app.state();
+          *state.really_quit.lock().unwrap() = true;
           app.exit(0);
         }
+        id if id.starts_with("channel_") => {
+          if let Ok(channel) = id.trim_start_matches("channel_").parse::<u8>() {
+            set_active_channel(app, channel);
+          }
+        }
         _ => {}
       }
     })
@@ -79,10 +223,18 @@ fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
           let _ = window.show();
           let _ = window.set_focus();
         }
+        refresh_tray_menu(app);
       }
     })
     .build(app)?;
 
+  let tray_state: State<TrayState> = app.state();
+  *tray_state.tray.lock().unwrap() = Some(tray);
+  *tray_state.show_item.lock().unwrap() = Some(tray_menu.show_item);
+  *tray_state.midi_status_item.lock().unwrap() = Some(tray_menu.midi_status_item);
+  *tray_state.check_update_item.lock().unwrap() = Some(tray_menu.check_update_item);
+  *tray_state.channel_items.lock().unwrap() = tray_menu.channel_items;
+
   Ok(())
 }
 
@@ -95,6 +247,17 @@ pub fn run() {
         let _ = window.set_focus();
       }
     }))
+    .plugin(
+      tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+          if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+            hotkeys::handle_shortcut(app, shortcut);
+          }
+        })
+        .build(),
+    )
+    .plugin(tauri_plugin_updater::Builder::new().build())
+    .plugin(tauri_plugin_process::init())
     .setup(|app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -104,14 +267,38 @@ pub fn run() {
         )?;
       }
 
+      // Load persisted settings before anything else depends on them
+      let settings = settings::load(&app.handle());
+      app.manage(SettingsState::new(settings.clone()));
+
       // Initialize app state
       let state = AppState {
-        always_on_top: Arc::new(Mutex::new(true)),
+        always_on_top: Arc::new(Mutex::new(settings.always_on_top)),
+        really_quit: Arc::new(Mutex::new(false)),
       };
       app.manage(state);
 
+      // Initialize MIDI output state
+      let midi_state = MidiState::new().map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+      app.manage(midi_state);
+      if let Some(port_name) = settings.selected_port_name.clone() {
+        let midi_state: State<MidiState> = app.state();
+        let _ = midi_state.connect_port(port_name);
+      }
+
+      // Initialize and register global hotkeys from persisted settings
+      app.manage(HotkeyState::new(settings.hotkeys.clone()));
+      hotkeys::register_all(&app.handle())?;
+
+      // Initialize the updater and store the tray icon handle
+      app.manage(UpdaterState::default());
+      let mut tray_state = TrayState::default();
+      tray_state.active_channel = Mutex::new(settings.active_channel);
+      app.manage(tray_state);
+
       // Create main window (it starts hidden as per config)
       let window = app.get_webview_window("main").unwrap();
+      window.set_always_on_top(settings.always_on_top)?;
 
       // Set up window event listeners
       let window_clone = window.clone();
@@ -123,6 +310,7 @@ pub fn run() {
             } else {
               let _ = window_clone.emit("app-blur", ());
             }
+            refresh_tray_menu(&window_clone.app_handle());
           }
           _ => {}
         }
@@ -134,9 +322,44 @@ pub fn run() {
       // Setup system tray
       setup_tray(&app.handle())?;
 
+      // Check for updates in the background on launch
+      let update_handle = app.handle().clone();
+      tauri::async_runtime::spawn(async move {
+        let _ = updater::check_for_updates(update_handle).await;
+      });
+
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![toggle_always_on_top, show_window])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .invoke_handler(tauri::generate_handler![
+      toggle_always_on_top,
+      show_window,
+      midi::list_midi_ports,
+      midi::connect_midi_port,
+      midi::create_virtual_port,
+      midi::send_raw_midi,
+      midi::note_on,
+      midi::note_off,
+      midi::control_change,
+      hotkeys::set_hotkey,
+      hotkeys::get_hotkeys,
+      updater::install_update,
+      settings::get_settings,
+      settings::save_settings,
+    ])
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      if let tauri::RunEvent::WindowEvent { label, event: tauri::WindowEvent::CloseRequested { api, .. }, .. } = event {
+        if label == "main" {
+          let state: State<AppState> = app.state();
+          if !*state.really_quit.lock().unwrap() {
+            api.prevent_close();
+            if let Some(window) = app.get_webview_window("main") {
+              let _ = window.hide();
+            }
+            refresh_tray_menu(app);
+          }
+        }
+      }
+    });
 }
@@ -0,0 +1,131 @@
+use std::sync::{Arc, Mutex};
+
+use midir::{MidiOutput, MidiOutputConnection, MidiOutputPort};
+
+/// Shared MIDI output state, managed alongside `AppState`.
+///
+/// `midir::MidiOutput` is consumed the moment it connects to a port, so it
+/// cannot be kept around as a reusable handle: a fresh one is created
+/// whenever ports need enumerating. The active `MidiOutputConnection` (real
+/// or virtual), once opened, is what's kept alive for sending.
+pub struct MidiState {
+  connection: Arc<Mutex<Option<MidiOutputConnection>>>,
+  connected_port_name: Mutex<Option<String>>,
+}
+
+impl MidiState {
+  pub fn new() -> Result<Self, String> {
+    // Fail fast if this platform's MIDI backend can't even initialize.
+    MidiOutput::new("kb2midi").map_err(|e| e.to_string())?;
+    Ok(Self {
+      connection: Arc::new(Mutex::new(None)),
+      connected_port_name: Mutex::new(None),
+    })
+  }
+
+  /// Name of the currently connected port, if any.
+  pub fn connected_port_name(&self) -> Option<String> {
+    self.connected_port_name.lock().unwrap().clone()
+  }
+
+  fn ports(&self) -> Result<(MidiOutput, Vec<MidiOutputPort>), String> {
+    let output = MidiOutput::new("kb2midi").map_err(|e| e.to_string())?;
+    let ports = output.ports();
+    Ok((output, ports))
+  }
+
+  pub fn list_ports(&self) -> Result<Vec<String>, String> {
+    let (output, ports) = self.ports()?;
+    let names = ports
+      .iter()
+      .map(|port| output.port_name(port).unwrap_or_else(|_| "Unknown port".to_string()))
+      .collect();
+    Ok(names)
+  }
+
+  pub fn connect_port(&self, index_or_name: String) -> Result<String, String> {
+    let (output, ports) = self.ports()?;
+
+    let port = if let Ok(index) = index_or_name.parse::<usize>() {
+      ports.get(index).cloned()
+    } else {
+      ports
+        .iter()
+        .find(|port| output.port_name(port).map(|n| n == index_or_name).unwrap_or(false))
+        .cloned()
+    };
+
+    let Some(port) = port else {
+      return Err(format!("no MIDI port matching `{index_or_name}`"));
+    };
+
+    let name = output.port_name(&port).map_err(|e| e.to_string())?;
+    let conn = output
+      .connect(&port, "kb2midi")
+      .map_err(|e| e.to_string())?;
+
+    *self.connection.lock().unwrap() = Some(conn);
+    *self.connected_port_name.lock().unwrap() = Some(name.clone());
+    Ok(name)
+  }
+
+  pub fn create_virtual_port(&self, name: String) -> Result<String, String> {
+    let output = MidiOutput::new("kb2midi").map_err(|e| e.to_string())?;
+    let conn = output
+      .create_virtual(&name)
+      .map_err(|e| e.to_string())?;
+
+    *self.connection.lock().unwrap() = Some(conn);
+    *self.connected_port_name.lock().unwrap() = Some(name.clone());
+    Ok(name)
+  }
+
+  pub fn send(&self, bytes: &[u8]) -> Result<(), String> {
+    let mut connection = self.connection.lock().unwrap();
+    let conn = connection
+      .as_mut()
+      .ok_or_else(|| "no MIDI port connected".to_string())?;
+    conn.send(bytes).map_err(|e| e.to_string())
+  }
+}
+
+#[tauri::command]
+pub fn list_midi_ports(state: tauri::State<MidiState>) -> Result<Vec<String>, String> {
+  state.list_ports()
+}
+
+#[tauri::command]
+pub fn connect_midi_port(app: tauri::AppHandle, state: tauri::State<MidiState>, index_or_name: String) -> Result<String, String> {
+  let name = state.connect_port(index_or_name)?;
+  crate::refresh_tray_menu(&app);
+  let _ = crate::settings::update(&app, |settings| settings.selected_port_name = Some(name.clone()));
+  Ok(name)
+}
+
+#[tauri::command]
+pub fn create_virtual_port(app: tauri::AppHandle, state: tauri::State<MidiState>, name: String) -> Result<String, String> {
+  let name = state.create_virtual_port(name)?;
+  crate::refresh_tray_menu(&app);
+  let _ = crate::settings::update(&app, |settings| settings.selected_port_name = Some(name.clone()));
+  Ok(name)
+}
+
+#[tauri::command]
+pub fn send_raw_midi(state: tauri::State<MidiState>, bytes: Vec<u8>) -> Result<(), String> {
+  state.send(&bytes)
+}
+
+#[tauri::command]
+pub fn note_on(state: tauri::State<MidiState>, channel: u8, note: u8, vel: u8) -> Result<(), String> {
+  state.send(&[0x90 | (channel & 0x0f), note & 0x7f, vel & 0x7f])
+}
+
+#[tauri::command]
+pub fn note_off(state: tauri::State<MidiState>, channel: u8, note: u8, vel: u8) -> Result<(), String> {
+  state.send(&[0x80 | (channel & 0x0f), note & 0x7f, vel & 0x7f])
+}
+
+#[tauri::command]
+pub fn control_change(state: tauri::State<MidiState>, channel: u8, controller: u8, value: u8) -> Result<(), String> {
+  state.send(&[0xb0 | (channel & 0x0f), controller & 0x7f, value & 0x7f])
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::hotkeys::{self, HotkeyAction};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Controller configuration that survives a restart.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  pub always_on_top: bool,
+  pub selected_port_name: Option<String>,
+  pub base_octave: i8,
+  pub key_mapping: HashMap<String, u8>,
+  pub active_channel: u8,
+  pub hotkeys: HashMap<HotkeyAction, String>,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      always_on_top: true,
+      selected_port_name: None,
+      base_octave: 4,
+      key_mapping: HashMap::new(),
+      active_channel: 0,
+      hotkeys: hotkeys::default_bindings(),
+    }
+  }
+}
+
+/// Holds the in-memory settings, kept in sync with the file on disk.
+#[derive(Default)]
+pub struct SettingsState(Mutex<Settings>);
+
+impl SettingsState {
+  pub fn new(settings: Settings) -> Self {
+    Self(Mutex::new(settings))
+  }
+
+  pub fn get(&self) -> Settings {
+    self.0.lock().unwrap().clone()
+  }
+
+  pub fn set(&self, settings: Settings) {
+    *self.0.lock().unwrap() = settings;
+  }
+}
+
+fn settings_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+  let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+  fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Load settings from the app config dir, falling back to defaults if the
+/// file is missing or unreadable.
+pub fn load(app: &AppHandle) -> Settings {
+  let mut settings: Settings = settings_path(app)
+    .ok()
+    .and_then(|path| fs::read_to_string(path).ok())
+    .and_then(|contents| serde_json::from_str(&contents).ok())
+    .unwrap_or_default();
+  for (action, accelerator) in hotkeys::default_bindings() {
+    settings.hotkeys.entry(action).or_insert(accelerator);
+  }
+  settings
+}
+
+/// Write settings to disk atomically (write to a temp file, then rename).
+pub fn persist(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+  let path = settings_path(app)?;
+  let tmp_path = path.with_extension("json.tmp");
+  let contents = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+  fs::write(&tmp_path, contents).map_err(|e| e.to_string())?;
+  fs::rename(&tmp_path, &path).map_err(|e| e.to_string())
+}
+
+/// Update the in-memory settings and persist them to disk.
+pub fn update(app: &AppHandle, f: impl FnOnce(&mut Settings)) -> Result<(), String> {
+  let state: State<SettingsState> = app.state();
+  let mut settings = state.get();
+  f(&mut settings);
+  state.set(settings.clone());
+  persist(app, &settings)
+}
+
+#[tauri::command]
+pub fn get_settings(state: State<SettingsState>) -> Settings {
+  state.get()
+}
+
+#[tauri::command]
+pub fn save_settings(app: AppHandle, state: State<SettingsState>, settings: Settings) -> Result<(), String> {
+  state.set(settings.clone());
+  persist(&app, &settings)
+}
@@ -0,0 +1,63 @@
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_process::ProcessExt;
+use tauri_plugin_updater::UpdaterExt;
+
+/// Tracks whether an update has finished downloading and is ready to be
+/// installed on next restart, so the tray menu can reflect it.
+#[derive(Default)]
+pub struct UpdaterState {
+  pub ready: Mutex<bool>,
+}
+
+/// Restart the app to apply an already-downloaded update.
+pub fn restart_to_update(app: &AppHandle) {
+  app.restart(&app.env());
+}
+
+/// Check for an update and, if one is found, download and install it in the
+/// background, emitting progress events to the main window along the way.
+/// If an update has already been downloaded and is waiting for a restart,
+/// restart immediately instead of checking again. Called once on launch and
+/// again from the tray's "Check for Updates…" / "Restart to Update" item.
+pub async fn check_for_updates(app: AppHandle) -> Result<(), String> {
+  {
+    let state: State<UpdaterState> = app.state();
+    if *state.ready.lock().unwrap() {
+      restart_to_update(&app);
+      return Ok(());
+    }
+  }
+
+  let updater = app.updater().map_err(|e| e.to_string())?;
+  let Some(update) = updater.check().await.map_err(|e| e.to_string())? else {
+    return Ok(());
+  };
+
+  let _ = app.emit("update-available", update.version.clone());
+
+  let mut downloaded = 0u64;
+  update
+    .download_and_install(
+      |chunk_len, total| {
+        downloaded += chunk_len as u64;
+        let _ = app.emit("update-progress", (downloaded, total));
+      },
+      || {},
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+  let state: State<UpdaterState> = app.state();
+  *state.ready.lock().unwrap() = true;
+  let _ = app.emit("update-ready", ());
+  crate::refresh_tray_menu(&app);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+  check_for_updates(app).await
+}